@@ -0,0 +1,211 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ring_buffer::ring_buffer::{RingBuffer, SmallRingBuffer};
+
+fn push_slice(c: &mut Criterion) {
+    let src: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+    let mut group = c.benchmark_group("push_slice");
+    group.bench_function("push", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u8> = RingBuffer::with_capacity(4096);
+            for &element in black_box(&src) {
+                buffer.push(element);
+            }
+            buffer
+        })
+    });
+    group.bench_function("push_slice_copy", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u8> = RingBuffer::with_capacity(4096);
+            buffer.push_slice_copy(black_box(&src));
+            buffer
+        })
+    });
+    group.finish();
+}
+
+fn push_batch(c: &mut Criterion) {
+    let src: Vec<u64> = (0..100_000).collect();
+    let mut group = c.benchmark_group("push_batch");
+    group.bench_function("push", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u64> = RingBuffer::with_capacity(4096);
+            for element in black_box(&src).clone() {
+                buffer.push(element);
+            }
+            buffer
+        })
+    });
+    group.bench_function("push_batch", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u64> = RingBuffer::with_capacity(4096);
+            buffer.push_batch(black_box(&src).clone());
+            buffer
+        })
+    });
+    group.finish();
+}
+
+fn overwrite(c: &mut Criterion) {
+    let frame: Vec<u32> = (0..1024).collect();
+    let mut group = c.benchmark_group("overwrite");
+    group.bench_function("clear_extend", |b| {
+        let mut buffer: RingBuffer<u32> = RingBuffer::with_capacity(1024);
+        b.iter(|| {
+            buffer.clear();
+            buffer.extend(black_box(&frame));
+        })
+    });
+    group.bench_function("overwrite_from", |b| {
+        let mut buffer: RingBuffer<u32> = RingBuffer::with_capacity(1024);
+        b.iter(|| buffer.overwrite_from(black_box(&frame)))
+    });
+    group.finish();
+}
+
+fn copied_vec(c: &mut Criterion) {
+    let mut buffer: RingBuffer<f64> = RingBuffer::with_capacity(100_000);
+    for i in 0..150_000 {
+        buffer.push(i as f64);
+    }
+    assert_eq!(buffer.iter().copied().collect::<Vec<f64>>(), buffer.copied_vec());
+    let mut group = c.benchmark_group("copied_vec");
+    group.bench_function("iter_collect", |b| {
+        b.iter(|| black_box(&buffer).iter().copied().collect::<Vec<f64>>())
+    });
+    group.bench_function("copied_vec", |b| b.iter(|| black_box(&buffer).copied_vec()));
+    group.finish();
+}
+
+fn collect(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collect");
+    group.bench_function("vec", |b| b.iter(|| (0..black_box(10_000u64)).collect::<Vec<u64>>()));
+    group.bench_function("ring_buffer", |b| {
+        b.iter(|| (0..black_box(10_000u64)).collect::<RingBuffer<u64>>())
+    });
+    group.finish();
+}
+
+fn drain_into_slice(c: &mut Criterion) {
+    let mut source: RingBuffer<u8> = RingBuffer::with_capacity(65_536);
+    for i in 0..100_000 {
+        source.push(i as u8);
+    }
+    let mut out: Vec<u8> = vec![0; 65_536];
+    let mut group = c.benchmark_group("drain_into_slice");
+    group.bench_function("pop", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u8> = source.clone();
+            for target in out.iter_mut() {
+                *target = buffer.pop().unwrap();
+            }
+            black_box(&out);
+        })
+    });
+    group.bench_function("drain_into_slice", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u8> = source.clone();
+            buffer.drain_into_slice(&mut out);
+            black_box(&out);
+        })
+    });
+    group.finish();
+}
+
+fn push_pop(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_pop");
+    for &capacity in &[4095, 4096] {
+        group.bench_function(format!("capacity_{}", capacity), |b| {
+            let mut buffer: RingBuffer<u32> = RingBuffer::with_capacity(capacity);
+            b.iter(|| {
+                for i in 0..10_000 {
+                    buffer.push(black_box(i));
+                }
+                let mut sum: u32 = 0;
+                for element in buffer.iter() {
+                    sum = sum.wrapping_add(*element);
+                }
+                while let Some(element) = buffer.pop() {
+                    sum = sum.wrapping_add(element);
+                }
+                sum
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Isolates the index reduction done by `wrap_index` at one capacity: the bit mask
+/// used for power of two capacities against the division used for the others.
+fn wrap_index(c: &mut Criterion) {
+    let capacity: usize = 4096;
+    let mut group = c.benchmark_group("wrap_index");
+    group.bench_function("mask", |b| {
+        b.iter(|| {
+            let mask: usize = black_box(capacity) - 1;
+            (0..10_000).fold(0, |sum: usize, index: usize| sum.wrapping_add(black_box(index) & mask))
+        })
+    });
+    group.bench_function("modulo", |b| {
+        b.iter(|| {
+            let capacity: usize = black_box(capacity);
+            (0..10_000).fold(0, |sum: usize, index: usize| sum.wrapping_add(black_box(index) % capacity))
+        })
+    });
+    group.finish();
+}
+
+fn grow_to(c: &mut Criterion) {
+    let mut source: RingBuffer<u64> = RingBuffer::with_capacity(100_000);
+    for i in 0..150_000 {
+        source.push(i);
+    }
+    let mut group = c.benchmark_group("grow_to");
+    group.bench_function("clone", |b| b.iter(|| black_box(&source).clone()));
+    group.bench_function("clone_grow_to", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u64> = black_box(&source).clone();
+            buffer.grow_to(200_000);
+            buffer
+        })
+    });
+    group.bench_function("clone_pop_into_new", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u64> = black_box(&source).clone();
+            let mut grown: RingBuffer<u64> = RingBuffer::with_capacity(200_000);
+            while let Some(element) = buffer.pop() {
+                grown.push(element);
+            }
+            grown
+        })
+    });
+    group.finish();
+}
+
+fn small(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small");
+    group.bench_function("ring_buffer", |b| {
+        b.iter(|| {
+            let mut buffer: RingBuffer<u64> = RingBuffer::with_capacity(8);
+            for i in 0..20 {
+                buffer.push(black_box(i));
+            }
+            buffer.pop()
+        })
+    });
+    group.bench_function("small_ring_buffer", |b| {
+        b.iter(|| {
+            let mut buffer: SmallRingBuffer<u64, 8> = SmallRingBuffer::with_capacity(8);
+            for i in 0..20 {
+                buffer.push(black_box(i));
+            }
+            buffer.pop()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches, push_slice, push_batch, overwrite, copied_vec, collect, drain_into_slice, push_pop, wrap_index, grow_to,
+    small
+);
+criterion_main!(benches);